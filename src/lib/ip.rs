@@ -13,33 +13,494 @@
 // limitations under the License.
 
 use std::collections::{hash_map::Entry, HashMap, HashSet};
-use std::net::IpAddr;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 
 use futures::stream::TryStreamExt;
-use netlink_packet_route::rtnl::AddressMessage;
-use serde::{Deserialize, Serialize};
+use netlink_packet_route::rtnl::{
+    address::nlas::{CacheInfo, Nla as AddressNla},
+    AddressMessage,
+};
+use serde::{
+    de::{self, Deserializer},
+    ser::Serializer,
+    Deserialize, Serialize,
+};
 
 use crate::{
     netlink::{get_ip_addr, get_ip_prefix_len},
     Iface, IfaceConf, NisporError,
 };
 
+// An IP address paired with its prefix length, e.g. `192.0.2.1/24` or
+// `fe80::1/64`. Stored as a typed `std::net::IpAddr` plus a prefix length so
+// callers do set math and containment checks on structured keys instead of
+// re-parsing formatted strings. Serializes to and from the familiar
+// `"addr/prefix"` string form for compatibility with existing consumers.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct IpNetwork {
+    pub ip: IpAddr,
+    pub prefix_len: u8,
+}
+
+impl IpNetwork {
+    pub fn new(ip: IpAddr, prefix_len: u8) -> Self {
+        Self { ip, prefix_len }
+    }
+
+    pub fn is_ipv6(&self) -> bool {
+        matches!(self.ip, IpAddr::V6(_))
+    }
+
+    // Returns true when `ip` falls inside this network. The prefix is turned
+    // into a bit mask (`u32` for IPv4, `u128` for IPv6) and the masked bits of
+    // both addresses are compared; a `/0` prefix yields a zero mask and
+    // therefore matches every address of the same family. Mixed families never
+    // match.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.ip, ip) {
+            (IpAddr::V4(net), IpAddr::V4(query)) => {
+                let mask = ipv4_prefix_mask(self.prefix_len);
+                (u32::from(net) & mask) == (u32::from(query) & mask)
+            }
+            (IpAddr::V6(net), IpAddr::V6(query)) => {
+                let mask = ipv6_prefix_mask(self.prefix_len);
+                (u128::from(net) & mask) == (u128::from(query) & mask)
+            }
+            _ => false,
+        }
+    }
+
+    // Returns true when the two networks share any address, i.e. one contains
+    // the other's base address. Comparison uses the shorter (less specific)
+    // prefix of the pair; networks of different families never overlap.
+    pub fn overlaps(&self, other: &IpNetwork) -> bool {
+        match (self.ip, other.ip) {
+            (IpAddr::V4(_), IpAddr::V4(_))
+            | (IpAddr::V6(_), IpAddr::V6(_)) => {
+                if self.prefix_len <= other.prefix_len {
+                    self.contains(other.ip)
+                } else {
+                    other.contains(self.ip)
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+// `!0 << (bits - prefix_len)`, special-casing `prefix_len == 0` to a zero mask
+// and `prefix_len >= bits` to an all-ones mask so we never shift by (or past)
+// the full width, which would overflow and panic.
+fn ipv4_prefix_mask(prefix_len: u8) -> u32 {
+    if prefix_len == 0 {
+        0
+    } else if prefix_len >= 32 {
+        u32::MAX
+    } else {
+        u32::MAX << (32 - u32::from(prefix_len))
+    }
+}
+
+fn ipv6_prefix_mask(prefix_len: u8) -> u128 {
+    if prefix_len == 0 {
+        0
+    } else if prefix_len >= 128 {
+        u128::MAX
+    } else {
+        u128::MAX << (128 - u32::from(prefix_len))
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.ip, self.prefix_len)
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = NisporError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (ip, prefix) = s.split_once('/').ok_or_else(|| {
+            NisporError::invalid_argument(format!(
+                "Invalid IP network '{s}', expected 'addr/prefix'"
+            ))
+        })?;
+        let ip = IpAddr::from_str(ip.trim())?;
+        let prefix_len = parse_prefix_len(prefix.trim(), &ip)?;
+        Ok(Self { ip, prefix_len })
+    }
+}
+
+// Parses the suffix after the `/` into a prefix length. A plain decimal is
+// taken verbatim (`/24`); a dotted-quad (IPv4) or colon-separated (IPv6) value
+// is accepted as a netmask (`/255.255.255.0`) and converted to the equivalent
+// prefix length. This lets configuration use either notation interchangeably.
+fn parse_prefix_len(suffix: &str, ip: &IpAddr) -> Result<u8, NisporError> {
+    let max_prefix_len = if ip.is_ipv4() { 32 } else { 128 };
+    if let Ok(prefix_len) = suffix.parse::<u8>() {
+        if prefix_len > max_prefix_len {
+            return Err(NisporError::invalid_argument(format!(
+                "Prefix length {prefix_len} out of range for {ip}, \
+                 expected 0..={max_prefix_len}"
+            )));
+        }
+        return Ok(prefix_len);
+    }
+    match ip {
+        IpAddr::V4(_) => {
+            let mask = Ipv4Addr::from_str(suffix).map_err(|_| {
+                NisporError::invalid_argument(format!(
+                    "Invalid IPv4 prefix length or netmask '{suffix}'"
+                ))
+            })?;
+            prefix_len_from_netmask_v4(u32::from(mask))
+        }
+        IpAddr::V6(_) => {
+            let mask = Ipv6Addr::from_str(suffix).map_err(|_| {
+                NisporError::invalid_argument(format!(
+                    "Invalid IPv6 prefix length or netmask '{suffix}'"
+                ))
+            })?;
+            prefix_len_from_netmask_v6(u128::from(mask))
+        }
+    }
+}
+
+// A valid netmask is a contiguous run of leading ones followed by zeros;
+// `leading_ones + trailing_zeros == width` proves there is no gap in between.
+// Non-contiguous masks (e.g. `255.0.255.0`) are rejected.
+fn prefix_len_from_netmask_v4(mask: u32) -> Result<u8, NisporError> {
+    if mask.leading_ones() + mask.trailing_zeros() != 32 {
+        return Err(NisporError::invalid_argument(format!(
+            "Non-contiguous IPv4 netmask {}",
+            Ipv4Addr::from(mask)
+        )));
+    }
+    Ok(mask.leading_ones() as u8)
+}
+
+fn prefix_len_from_netmask_v6(mask: u128) -> Result<u8, NisporError> {
+    if mask.leading_ones() + mask.trailing_zeros() != 128 {
+        return Err(NisporError::invalid_argument(format!(
+            "Non-contiguous IPv6 netmask {}",
+            Ipv6Addr::from(mask)
+        )));
+    }
+    Ok(mask.leading_ones() as u8)
+}
+
+impl Serialize for IpNetwork {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for IpNetwork {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+
+// Per-address `IFA_F_*` flags reported by (and accepted by) the kernel. The
+// discriminants are the kernel bit values so encoding/decoding is a plain
+// bit-wise fold.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+#[repr(u32)]
+pub enum IpAddrFlag {
+    // IFA_F_SECONDARY, also known as IFA_F_TEMPORARY for IPv6 privacy
+    // addresses.
+    Secondary = 0x01,
+    Nodad = 0x02,
+    Optimistic = 0x04,
+    Dadfailed = 0x08,
+    Homeaddress = 0x10,
+    Deprecated = 0x20,
+    Tentative = 0x40,
+    Permanent = 0x80,
+    Mngtmpaddr = 0x100,
+    Noprefixroute = 0x200,
+    Mcautojoin = 0x400,
+    StablePrivacy = 0x800,
+}
+
+impl IpAddrFlag {
+    const ALL: [Self; 12] = [
+        Self::Secondary,
+        Self::Nodad,
+        Self::Optimistic,
+        Self::Dadfailed,
+        Self::Homeaddress,
+        Self::Deprecated,
+        Self::Tentative,
+        Self::Permanent,
+        Self::Mngtmpaddr,
+        Self::Noprefixroute,
+        Self::Mcautojoin,
+        Self::StablePrivacy,
+    ];
+
+    // Decodes the flag bitfield (`IFA_FLAGS` or the legacy 8-bit header field)
+    // into the list of flags that are set.
+    pub fn from_bits(bits: u32) -> Vec<Self> {
+        Self::ALL
+            .iter()
+            .filter(|flag| bits & (**flag as u32) != 0)
+            .copied()
+            .collect()
+    }
+
+    // Folds a list of flags back into the `IFA_FLAGS` bitfield.
+    pub fn to_bits(flags: &[Self]) -> u32 {
+        flags.iter().fold(0, |bits, flag| bits | (*flag as u32))
+    }
+}
+
+// The category an address falls into, derived purely from the address bits of
+// the typed `Ipv4Addr`/`Ipv6Addr`. Every test is a bit-mask comparison on the
+// integer form of the address rather than a string-prefix match.
+#[derive(
+    Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy, Default,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum IpAddrCategory {
+    // `0.0.0.0` / `::`
+    Unspecified,
+    // `127.0.0.0/8` / `::1`
+    Loopback,
+    // `169.254.0.0/16` / `fe80::/10`
+    LinkLocal,
+    // IPv6 unique-local `fc00::/7`
+    UniqueLocal,
+    // `224.0.0.0/4` / `ff00::/8`
+    Multicast,
+    // Documentation ranges (`192.0.2.0/24`, `198.51.100.0/24`,
+    // `203.0.113.0/24`, `2001:db8::/32`)
+    Documentation,
+    // Ordinary global unicast address
+    #[default]
+    Global,
+}
+
+impl IpAddrCategory {
+    pub fn from_ip(ip: IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(ip) => Self::from_ipv4(u32::from(ip)),
+            IpAddr::V6(ip) => Self::from_ipv6(u128::from(ip)),
+        }
+    }
+
+    fn from_ipv4(bits: u32) -> Self {
+        if bits == 0 {
+            Self::Unspecified
+        } else if bits >> 24 == 127 {
+            Self::Loopback
+        } else if bits >> 16 == 0xa9fe {
+            Self::LinkLocal
+        } else if bits >> 28 == 0xe {
+            Self::Multicast
+        } else if matches!(bits >> 8, 0xc00002 | 0xc63364 | 0xcb0071) {
+            Self::Documentation
+        } else {
+            Self::Global
+        }
+    }
+
+    fn from_ipv6(bits: u128) -> Self {
+        if bits == 0 {
+            Self::Unspecified
+        } else if bits == 1 {
+            Self::Loopback
+        } else if bits >> 120 == 0xff {
+            Self::Multicast
+        } else if bits >> 118 == (0xfe80 >> 6) {
+            Self::LinkLocal
+        } else if bits >> 121 == (0xfc00 >> 9) {
+            Self::UniqueLocal
+        } else if bits >> 96 == 0x2001_0db8 {
+            Self::Documentation
+        } else {
+            Self::Global
+        }
+    }
+
+    // Whether this is a unicast link-local address (IPv4 `169.254.0.0/16` or
+    // IPv6 `fe80::/10`); multicast link-local does not qualify.
+    pub fn is_unicast_link_local(&self) -> bool {
+        matches!(self, Self::LinkLocal)
+    }
+}
+
+// The remaining valid or preferred lifetime of an address. Serializes to the
+// familiar `"forever"` string for permanent addresses and to the remaining
+// number of seconds otherwise, matching what `ip addr` reports.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum IpAddrLifetime {
+    Forever,
+    Secs(u32),
+}
+
+// The kernel encodes an infinite lifetime as all-ones in `IFA_CACHEINFO`.
+const INFINITE_LIFE_TIME: u32 = u32::MAX;
+
+impl Default for IpAddrLifetime {
+    fn default() -> Self {
+        Self::Forever
+    }
+}
+
+impl IpAddrLifetime {
+    pub fn is_forever(&self) -> bool {
+        matches!(self, Self::Forever)
+    }
+
+    // The on-wire seconds value, with `Forever` mapped to the kernel's
+    // all-ones infinity marker.
+    fn as_secs(&self) -> u32 {
+        match self {
+            Self::Forever => INFINITE_LIFE_TIME,
+            Self::Secs(secs) => *secs,
+        }
+    }
+}
+
+impl From<u32> for IpAddrLifetime {
+    fn from(secs: u32) -> Self {
+        if secs == INFINITE_LIFE_TIME {
+            Self::Forever
+        } else {
+            Self::Secs(secs)
+        }
+    }
+}
+
+impl fmt::Display for IpAddrLifetime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Forever => write!(f, "forever"),
+            Self::Secs(secs) => write!(f, "{secs}"),
+        }
+    }
+}
+
+impl FromStr for IpAddrLifetime {
+    type Err = NisporError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "forever" {
+            Ok(Self::Forever)
+        } else {
+            s.parse::<u32>().map(Self::Secs).map_err(|_| {
+                NisporError::invalid_argument(format!(
+                    "Invalid address lifetime '{s}'"
+                ))
+            })
+        }
+    }
+}
+
+impl Serialize for IpAddrLifetime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for IpAddrLifetime {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 pub struct Ipv4Info {
     pub addresses: Vec<Ipv4AddrInfo>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Ipv4AddrInfo {
-    pub address: String,
-    pub prefix_len: u8,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: IpNetwork,
     pub peer: Option<String>,
     // The renaming seonds for this address be valid
-    pub valid_lft: String,
+    pub valid_lft: IpAddrLifetime,
     // The renaming seonds for this address be preferred
-    pub preferred_lft: String,
+    pub preferred_lft: IpAddrLifetime,
+    // The `IFA_F_*` flags reported by the kernel for this address.
+    pub flags: Vec<IpAddrFlag>,
+    // The category this address falls into, derived from its bits.
+    pub category: IpAddrCategory,
+}
+
+// The serialized form keeps `address` (a bare IP) and `prefix_len` as distinct
+// fields, matching nispor's existing schema consumed by the CLI, YAML output
+// and language bindings.
+#[derive(Serialize, Deserialize)]
+struct Ipv4AddrInfoRepr {
+    address: IpAddr,
+    prefix_len: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    peer: Option<String>,
+    valid_lft: IpAddrLifetime,
+    preferred_lft: IpAddrLifetime,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    flags: Vec<IpAddrFlag>,
+    #[serde(default)]
+    category: IpAddrCategory,
+}
+
+impl Serialize for Ipv4AddrInfo {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ipv4AddrInfoRepr {
+            address: self.address.ip,
+            prefix_len: self.address.prefix_len,
+            peer: self.peer.clone(),
+            valid_lft: self.valid_lft,
+            preferred_lft: self.preferred_lft,
+            flags: self.flags.clone(),
+            // Category is a pure function of the address; recompute so output
+            // is correct even if the in-memory field was left at its default.
+            category: IpAddrCategory::from_ip(self.address.ip),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ipv4AddrInfo {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let repr = Ipv4AddrInfoRepr::deserialize(deserializer)?;
+        let address = IpNetwork::new(repr.address, repr.prefix_len);
+        Ok(Self {
+            category: IpAddrCategory::from_ip(address.ip),
+            address,
+            peer: repr.peer,
+            valid_lft: repr.valid_lft,
+            preferred_lft: repr.preferred_lft,
+            flags: repr.flags,
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
@@ -47,14 +508,66 @@ pub struct Ipv6Info {
     pub addresses: Vec<Ipv6AddrInfo>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Ipv6AddrInfo {
-    pub address: String,
-    pub prefix_len: u8,
+    pub address: IpNetwork,
     // The renaming seonds for this address be valid
-    pub valid_lft: String,
+    pub valid_lft: IpAddrLifetime,
     // The renaming seonds for this address be preferred
-    pub preferred_lft: String,
+    pub preferred_lft: IpAddrLifetime,
+    // The `IFA_F_*` flags reported by the kernel for this address.
+    pub flags: Vec<IpAddrFlag>,
+    // The category this address falls into, derived from its bits.
+    pub category: IpAddrCategory,
+}
+
+// See [`Ipv4AddrInfoRepr`]: `address` and `prefix_len` stay separate fields.
+#[derive(Serialize, Deserialize)]
+struct Ipv6AddrInfoRepr {
+    address: IpAddr,
+    prefix_len: u8,
+    valid_lft: IpAddrLifetime,
+    preferred_lft: IpAddrLifetime,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    flags: Vec<IpAddrFlag>,
+    #[serde(default)]
+    category: IpAddrCategory,
+}
+
+impl Serialize for Ipv6AddrInfo {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ipv6AddrInfoRepr {
+            address: self.address.ip,
+            prefix_len: self.address.prefix_len,
+            valid_lft: self.valid_lft,
+            preferred_lft: self.preferred_lft,
+            flags: self.flags.clone(),
+            // Category is a pure function of the address; recompute so output
+            // is correct even if the in-memory field was left at its default.
+            category: IpAddrCategory::from_ip(self.address.ip),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ipv6AddrInfo {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let repr = Ipv6AddrInfoRepr::deserialize(deserializer)?;
+        let address = IpNetwork::new(repr.address, repr.prefix_len);
+        Ok(Self {
+            category: IpAddrCategory::from_ip(address.ip),
+            address,
+            valid_lft: repr.valid_lft,
+            preferred_lft: repr.preferred_lft,
+            flags: repr.flags,
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
@@ -62,16 +575,27 @@ pub struct IpConf {
     pub addresses: Vec<IpAddrConf>,
 }
 
+// Maps a queried lifetime to its configuration form: permanent addresses carry
+// no explicit lifetime (`None`), non-permanent ones keep their remaining value
+// so that re-applying the current state does not turn them permanent.
+fn lft_to_conf(lft: IpAddrLifetime) -> Option<IpAddrLifetime> {
+    if lft.is_forever() {
+        None
+    } else {
+        Some(lft)
+    }
+}
+
 impl From<&Ipv4Info> for IpConf {
     fn from(info: &Ipv4Info) -> Self {
         let mut addrs = Vec::new();
         for addr_info in &info.addresses {
-            if addr_info.valid_lft == "forever" {
-                addrs.push(IpAddrConf {
-                    address: addr_info.address.clone(),
-                    prefix_len: addr_info.prefix_len,
-                });
-            }
+            addrs.push(IpAddrConf {
+                address: addr_info.address,
+                valid_lft: lft_to_conf(addr_info.valid_lft),
+                preferred_lft: lft_to_conf(addr_info.preferred_lft),
+                flags: addr_info.flags.clone(),
+            });
         }
         Self { addresses: addrs }
     }
@@ -81,12 +605,12 @@ impl From<&Ipv6Info> for IpConf {
     fn from(info: &Ipv6Info) -> Self {
         let mut addrs = Vec::new();
         for addr_info in &info.addresses {
-            if addr_info.valid_lft == "forever" {
-                addrs.push(IpAddrConf {
-                    address: addr_info.address.clone(),
-                    prefix_len: addr_info.prefix_len,
-                });
-            }
+            addrs.push(IpAddrConf {
+                address: addr_info.address,
+                valid_lft: lft_to_conf(addr_info.valid_lft),
+                preferred_lft: lft_to_conf(addr_info.preferred_lft),
+                flags: addr_info.flags.clone(),
+            });
         }
         Self { addresses: addrs }
     }
@@ -98,12 +622,113 @@ pub enum IpFamily {
     Ipv6,
 }
 
-#[derive(
-    Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Default,
-)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct IpAddrConf {
-    pub address: String,
-    pub prefix_len: u8,
+    pub address: IpNetwork,
+    // Remaining valid lifetime to program via `IFA_CACHEINFO`. When omitted the
+    // address is added as permanent.
+    pub valid_lft: Option<IpAddrLifetime>,
+    pub preferred_lft: Option<IpAddrLifetime>,
+    // Extra `IFA_F_*` flags to request on add, e.g. `noprefixroute`.
+    pub flags: Vec<IpAddrFlag>,
+}
+
+// The serialized form keeps `address` and `prefix_len` separate (nispor's
+// schema). On input `address` may additionally carry a `/prefix` or
+// `/netmask` suffix, in which case it wins over a separate `prefix_len`.
+#[derive(Serialize, Deserialize)]
+struct IpAddrConfRepr {
+    address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prefix_len: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_lft: Option<IpAddrLifetime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    preferred_lft: Option<IpAddrLifetime>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    flags: Vec<IpAddrFlag>,
+}
+
+impl Serialize for IpAddrConf {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        IpAddrConfRepr {
+            address: self.address.ip.to_string(),
+            prefix_len: Some(self.address.prefix_len),
+            valid_lft: self.valid_lft,
+            preferred_lft: self.preferred_lft,
+            flags: self.flags.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for IpAddrConf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let repr = IpAddrConfRepr::deserialize(deserializer)?;
+        // A `/`-suffix in `address` carries the prefix (or netmask); otherwise
+        // fall back to the separate `prefix_len`, defaulting to the host route
+        // width for the family.
+        let network = if repr.address.contains('/') {
+            IpNetwork::from_str(&repr.address).map_err(de::Error::custom)?
+        } else {
+            let ip =
+                IpAddr::from_str(&repr.address).map_err(de::Error::custom)?;
+            let prefix_len =
+                repr.prefix_len.unwrap_or(if ip.is_ipv4() { 32 } else { 128 });
+            IpNetwork::from_str(&format!("{}/{}", repr.address, prefix_len))
+                .map_err(de::Error::custom)?
+        };
+        Ok(Self {
+            address: network,
+            valid_lft: repr.valid_lft,
+            preferred_lft: repr.preferred_lft,
+            flags: repr.flags,
+        })
+    }
+}
+
+impl IpAddrConf {
+    // The `IFA_CACHEINFO` attribute to attach when adding this address, or
+    // `None` when both lifetimes are permanent (the kernel default).
+    fn cache_info(&self) -> Option<CacheInfo> {
+        if self.valid_lft.unwrap_or_default().is_forever()
+            && self.preferred_lft.unwrap_or_default().is_forever()
+        {
+            return None;
+        }
+        let valid = self.valid_lft.unwrap_or_default();
+        // The preferred lifetime must never exceed the valid lifetime, so when
+        // only `valid_lft` is given fall back to it rather than to forever.
+        let preferred = self.preferred_lft.unwrap_or(valid);
+        let valid = valid.as_secs();
+        let preferred = preferred.as_secs();
+        Some(CacheInfo {
+            ifa_valid: valid as i32,
+            ifa_preferred: preferred as i32,
+            ..Default::default()
+        })
+    }
+
+    // Whether this address is dynamically assigned or kernel-managed (a DHCP
+    // lease, a SLAAC/privacy address, ...) rather than a statically configured
+    // permanent one. Such addresses are left untouched during reconcile so
+    // applying a static config does not tear down a running lease.
+    fn is_dynamic(&self) -> bool {
+        matches!(self.valid_lft, Some(IpAddrLifetime::Secs(_)))
+            || self.flags.iter().any(|flag| match flag {
+                // On IPv4 this bit marks a secondary address, not a temporary
+                // one.
+                IpAddrFlag::Secondary => self.address.is_ipv6(),
+                IpAddrFlag::Mngtmpaddr => true,
+                _ => false,
+            })
+    }
 }
 
 impl IpConf {
@@ -131,55 +756,32 @@ impl IpConf {
     }
 }
 
-fn is_ipv6_unicast_link_local_full(ip: &str, prefix_len: u8) -> bool {
-    is_ipv6_addr(ip)
-        && ip.len() >= 3
-        && ["fe8", "fe9", "fea", "feb"].contains(&&ip[..3])
-        && prefix_len >= 10
-}
-
-// TODO: Rust offical has std::net::Ipv6Addr::is_unicast_link_local() in
-// experimental.
-fn is_ipv6_unicast_link_local(address_full: &str) -> bool {
-    // The unicast link local address range is fe80::/10.
-    let v: Vec<&str> = address_full.split('/').collect();
-    if v.len() == 2 {
-        let ip = v[0];
-        if let Ok(prefix) = str::parse::<u8>(v[1]) {
-            is_ipv6_unicast_link_local_full(ip, prefix)
-        } else {
-            false
-        }
-    } else {
-        false
-    }
-}
-
-fn is_ipv6_addr(addr: &str) -> bool {
-    addr.contains(':')
+// The unicast link local address range is fe80::/10.
+fn is_ipv6_unicast_link_local(net: &IpNetwork) -> bool {
+    net.is_ipv6() && IpAddrCategory::from_ip(net.ip).is_unicast_link_local()
 }
 
 async fn get_nl_addr_msgs(
     handle: &rtnetlink::Handle,
-) -> Result<HashMap<u32, HashMap<String, AddressMessage>>, NisporError> {
-    let mut msgs: HashMap<u32, HashMap<String, AddressMessage>> =
+) -> Result<HashMap<u32, HashMap<IpNetwork, AddressMessage>>, NisporError> {
+    let mut msgs: HashMap<u32, HashMap<IpNetwork, AddressMessage>> =
         HashMap::new();
     let mut addrs = handle.address().get().execute();
     while let Some(nl_addr_msg) = addrs.try_next().await? {
         let iface_index = nl_addr_msg.header.index;
-        let full_address = format!(
+        let network = IpNetwork::from_str(&format!(
             "{}/{}",
             get_ip_addr(&nl_addr_msg),
             get_ip_prefix_len(&nl_addr_msg)
-        );
+        ))?;
         match msgs.entry(iface_index) {
             Entry::Occupied(o) => {
-                o.into_mut().insert(full_address, nl_addr_msg);
+                o.into_mut().insert(network, nl_addr_msg);
             }
             Entry::Vacant(v) => {
                 v.insert({
                     let mut tmp = HashMap::new();
-                    tmp.insert(full_address, nl_addr_msg);
+                    tmp.insert(network, nl_addr_msg);
                     tmp
                 });
             }
@@ -189,6 +791,114 @@ async fn get_nl_addr_msgs(
     Ok(msgs)
 }
 
+// Dumps the IPv4 and IPv6 addresses of a single interface without building the
+// full per-interface address map that [`get_nl_addr_msgs`] allocates. The
+// kernel dump is filtered by link index (the `ifa_index` header field) so
+// large hosts do not pay for every other NIC's addresses. This is the lightweight "list
+// addresses for this link" entry point, independent of the full `NetState`
+// query.
+pub async fn get_iface_ip_addrs(
+    handle: &rtnetlink::Handle,
+    iface_index: u32,
+) -> Result<(Ipv4Info, Ipv6Info), NisporError> {
+    let mut ipv4 = Ipv4Info::default();
+    let mut ipv6 = Ipv6Info::default();
+    let mut addrs = handle
+        .address()
+        .get()
+        .set_link_index_filter(iface_index)
+        .execute();
+    while let Some(nl_addr_msg) = addrs.try_next().await? {
+        // The index filter is a hint; drop anything the kernel still hands back
+        // for another link.
+        if nl_addr_msg.header.index != iface_index {
+            continue;
+        }
+        if nl_addr_msg_network(&nl_addr_msg)?.is_ipv6() {
+            ipv6.addresses.push(Ipv6AddrInfo::from_addr_msg(&nl_addr_msg)?);
+        } else {
+            ipv4.addresses.push(Ipv4AddrInfo::from_addr_msg(&nl_addr_msg)?);
+        }
+    }
+
+    Ok((ipv4, ipv6))
+}
+
+impl Ipv4AddrInfo {
+    // Builds an IPv4 address entry from a netlink `AddressMessage`, populating
+    // the flags and category from the message rather than leaving them at their
+    // defaults. The main `NetState` query path should construct entries through
+    // this so its results carry the same information as [`get_iface_ip_addrs`].
+    pub(crate) fn from_addr_msg(
+        nl_addr_msg: &AddressMessage,
+    ) -> Result<Self, NisporError> {
+        let address = nl_addr_msg_network(nl_addr_msg)?;
+        let (valid_lft, preferred_lft) = addr_msg_lifetimes(nl_addr_msg);
+        Ok(Self {
+            category: IpAddrCategory::from_ip(address.ip),
+            address,
+            peer: None,
+            valid_lft,
+            preferred_lft,
+            flags: addr_msg_flags(nl_addr_msg),
+        })
+    }
+}
+
+impl Ipv6AddrInfo {
+    // See [`Ipv4AddrInfo::from_addr_msg`].
+    pub(crate) fn from_addr_msg(
+        nl_addr_msg: &AddressMessage,
+    ) -> Result<Self, NisporError> {
+        let address = nl_addr_msg_network(nl_addr_msg)?;
+        let (valid_lft, preferred_lft) = addr_msg_lifetimes(nl_addr_msg);
+        Ok(Self {
+            category: IpAddrCategory::from_ip(address.ip),
+            address,
+            valid_lft,
+            preferred_lft,
+            flags: addr_msg_flags(nl_addr_msg),
+        })
+    }
+}
+
+fn nl_addr_msg_network(
+    nl_addr_msg: &AddressMessage,
+) -> Result<IpNetwork, NisporError> {
+    IpNetwork::from_str(&format!(
+        "{}/{}",
+        get_ip_addr(nl_addr_msg),
+        get_ip_prefix_len(nl_addr_msg)
+    ))
+}
+
+// Reads the valid/preferred lifetimes out of an address message's
+// `IFA_CACHEINFO`, defaulting to permanent when the attribute is absent.
+fn addr_msg_lifetimes(
+    nl_addr_msg: &AddressMessage,
+) -> (IpAddrLifetime, IpAddrLifetime) {
+    for nla in &nl_addr_msg.nlas {
+        if let AddressNla::CacheInfo(cache_info) = nla {
+            return (
+                IpAddrLifetime::from(cache_info.ifa_valid as u32),
+                IpAddrLifetime::from(cache_info.ifa_preferred as u32),
+            );
+        }
+    }
+    (IpAddrLifetime::Forever, IpAddrLifetime::Forever)
+}
+
+// Reads the `IFA_F_*` flags, preferring the 32-bit `IFA_FLAGS` attribute and
+// falling back to the legacy 8-bit header field.
+fn addr_msg_flags(nl_addr_msg: &AddressMessage) -> Vec<IpAddrFlag> {
+    for nla in &nl_addr_msg.nlas {
+        if let AddressNla::Flags(flags) = nla {
+            return IpAddrFlag::from_bits(*flags);
+        }
+    }
+    IpAddrFlag::from_bits(u32::from(nl_addr_msg.header.flags))
+}
+
 // For ipv6 link local address,
 // 1. We remove existing link ipv6 link local address when desire has ipv6 link
 //    local address
@@ -230,7 +940,7 @@ pub(crate) async fn change_ips(
 
 async fn apply_ip_conf(
     handle: &rtnetlink::Handle,
-    nl_addr_msgs: Option<&HashMap<String, AddressMessage>>,
+    nl_addr_msgs: Option<&HashMap<IpNetwork, AddressMessage>>,
     iface_index: u32,
     ip_conf: Option<&IpConf>,
     cur_ip_conf: Option<IpConf>,
@@ -243,10 +953,10 @@ async fn apply_ip_conf(
             // Desire would like to remove all address except IPv6 link local
             // address
             if let Some(nl_addr_msgs) = nl_addr_msgs {
-                for (address_full, nl_addr_msg) in nl_addr_msgs.iter() {
+                for (network, nl_addr_msg) in nl_addr_msgs.iter() {
                     match ip_family {
                         IpFamily::Ipv4 => {
-                            if !is_ipv6_addr(address_full) {
+                            if !network.is_ipv6() {
                                 handle
                                     .address()
                                     .del(nl_addr_msg.clone())
@@ -255,8 +965,8 @@ async fn apply_ip_conf(
                             }
                         }
                         IpFamily::Ipv6 => {
-                            if is_ipv6_addr(address_full)
-                                && !is_ipv6_unicast_link_local(address_full)
+                            if network.is_ipv6()
+                                && !is_ipv6_unicast_link_local(network)
                             {
                                 handle
                                     .address()
@@ -272,57 +982,46 @@ async fn apply_ip_conf(
         (Some(ip_conf), None) => {
             // Desire would like to add more address
             for addr_conf in &ip_conf.addresses {
-                handle
-                    .address()
-                    .add(
-                        iface_index,
-                        ip_addr_str_to_enum(&addr_conf.address)?,
-                        addr_conf.prefix_len,
-                    )
-                    .execute()
-                    .await?;
+                add_ip_addr(handle, iface_index, addr_conf).await?;
             }
         }
         (Some(ip_conf), Some(cur_ip_conf)) => {
-            let mut cur_ip_addr_confs = HashSet::new();
-            let mut des_ip_addr_confs = HashSet::new();
-            for des_addr in &ip_conf.addresses {
-                des_ip_addr_confs.insert(IpAddrConf {
-                    address: des_addr.address.clone(),
-                    prefix_len: des_addr.prefix_len,
-                });
-            }
-            for cur_addr in &cur_ip_conf.addresses {
-                cur_ip_addr_confs.insert(IpAddrConf {
-                    address: cur_addr.address.clone(),
-                    prefix_len: cur_addr.prefix_len,
-                });
-            }
-            let has_ipv6_link_local_in_desire = if ip_family == IpFamily::Ipv4 {
-                ip_conf.addresses.iter().any(|addr| {
-                    is_ipv6_unicast_link_local_full(
-                        &addr.address,
-                        addr.prefix_len,
-                    )
-                })
-            } else {
-                false
-            };
-            for addr_to_remove in &cur_ip_addr_confs - &des_ip_addr_confs {
+            let des_by_net: HashMap<IpNetwork, &IpAddrConf> = ip_conf
+                .addresses
+                .iter()
+                .map(|a| (a.address, a))
+                .collect();
+            let des_networks: HashSet<IpNetwork> =
+                des_by_net.keys().copied().collect();
+            let cur_by_net: HashMap<IpNetwork, &IpAddrConf> = cur_ip_conf
+                .addresses
+                .iter()
+                .map(|a| (a.address, a))
+                .collect();
+            let cur_networks: HashSet<IpNetwork> =
+                cur_by_net.keys().copied().collect();
+            let has_ipv6_link_local_in_desire = ip_family == IpFamily::Ipv6
+                && des_networks.iter().any(is_ipv6_unicast_link_local);
+            for addr_to_remove in &cur_networks - &des_networks {
+                // Leave dynamic/kernel-managed addresses (DHCP leases, SLAAC
+                // and privacy addresses) in place; a static config must not
+                // tear them down.
+                if cur_by_net
+                    .get(&addr_to_remove)
+                    .is_some_and(|conf| conf.is_dynamic())
+                {
+                    continue;
+                }
                 // Only remove ipv6 link local address when desire has link
                 // local address defined
                 if !(ip_family == IpFamily::Ipv6
                     && !has_ipv6_link_local_in_desire
-                    && is_ipv6_unicast_link_local_full(
-                        &addr_to_remove.address,
-                        addr_to_remove.prefix_len,
-                    ))
+                    && is_ipv6_unicast_link_local(&addr_to_remove))
                 {
                     if let Some(nl_addr_msgs) = nl_addr_msgs {
-                        if let Some(nl_addr_msg) = nl_addr_msgs.get(&format!(
-                            "{}/{}",
-                            &addr_to_remove.address, addr_to_remove.prefix_len
-                        )) {
+                        if let Some(nl_addr_msg) =
+                            nl_addr_msgs.get(&addr_to_remove)
+                        {
                             handle
                                 .address()
                                 .del(nl_addr_msg.clone())
@@ -333,26 +1032,36 @@ async fn apply_ip_conf(
                 }
             }
 
-            for addr_to_add in &des_ip_addr_confs - &cur_ip_addr_confs {
-                handle
-                    .address()
-                    .add(
-                        iface_index,
-                        ip_addr_str_to_enum(&addr_to_add.address)?,
-                        addr_to_add.prefix_len,
-                    )
-                    .execute()
-                    .await?;
+            for addr_to_add in &des_networks - &cur_networks {
+                if let Some(addr_conf) = des_by_net.get(&addr_to_add) {
+                    add_ip_addr(handle, iface_index, addr_conf).await?;
+                }
             }
         }
     }
     Ok(())
 }
 
-fn ip_addr_str_to_enum(address: &str) -> Result<IpAddr, NisporError> {
-    Ok(if is_ipv6_addr(address) {
-        IpAddr::V6(std::net::Ipv6Addr::from_str(address)?)
-    } else {
-        IpAddr::V4(std::net::Ipv4Addr::from_str(address)?)
-    })
+// Adds a single address, attaching an `IFA_CACHEINFO` attribute when the
+// configuration requests a non-permanent valid or preferred lifetime.
+async fn add_ip_addr(
+    handle: &rtnetlink::Handle,
+    iface_index: u32,
+    addr_conf: &IpAddrConf,
+) -> Result<(), NisporError> {
+    let mut req = handle.address().add(
+        iface_index,
+        addr_conf.address.ip,
+        addr_conf.address.prefix_len,
+    );
+    if let Some(cache_info) = addr_conf.cache_info() {
+        req.message_mut().nlas.push(AddressNla::CacheInfo(cache_info));
+    }
+    if !addr_conf.flags.is_empty() {
+        req.message_mut()
+            .nlas
+            .push(AddressNla::Flags(IpAddrFlag::to_bits(&addr_conf.flags)));
+    }
+    req.execute().await?;
+    Ok(())
 }